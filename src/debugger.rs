@@ -0,0 +1,133 @@
+//! An imgui overlay for poking at live simulation parameters, in the spirit
+//! of doukutsu-rs's live debugger: drawn on top of `PlayScene` each frame,
+//! it never touches the deterministic step itself, only the knobs that feed
+//! into it.
+
+use ggez::graphics::{self, gfx_device_gl};
+use ggez::{Context, GameResult};
+
+use imgui::{ImGui, Ui};
+use imgui_gfx_renderer::{Renderer, Shaders};
+
+/// Tunable simulation parameters a designer might want to sweep at runtime
+/// instead of recompiling. Read by `PlayScene::step_frame` and friends in
+/// place of the constants they used to be. `Copy` so `PlayScene` can stash
+/// one alongside each rollback `Snapshot` without fighting the borrow
+/// checker over it.
+#[derive(Clone, Copy)]
+pub struct Tunables {
+    pub paddle_speed: f32,
+    pub lerp_tweak: f32,
+    pub ball_speed: f32,
+    pub restitution: f32,
+    pub mass: f32,
+    pub gravity: f32,
+}
+
+impl Default for Tunables {
+    fn default() -> Self {
+        Tunables {
+            paddle_speed: 1000.0,
+            lerp_tweak: 10.0,
+            ball_speed: 1000.0,
+            restitution: 1.0,
+            mass: 1.0,
+            gravity: 0.0,
+        }
+    }
+}
+
+/// What the overlay's buttons asked `PlayScene` to do this frame, on top of
+/// whatever its sliders already wrote into `Tunables`. `PlayScene` only
+/// acts on these from its own `update`, so a click can't mutate the match
+/// while some other scene (e.g. a pushed `PauseScene`) is on top and
+/// `update` isn't even being called.
+#[derive(Default)]
+pub struct DebugCommands {
+    pub toggle_pause: bool,
+    pub reserve: bool,
+}
+
+pub struct LiveDebugger {
+    imgui: ImGui,
+    renderer: Renderer<gfx_device_gl::Resources>,
+}
+
+impl LiveDebugger {
+    /// Needs `ctx` up front (unlike the rest of `PlayScene`'s fields) to pull
+    /// the gfx factory and the screen's render target out of it for the
+    /// renderer: imgui itself only builds a vertex/index buffer each frame,
+    /// it doesn't know how to push that onto ggez's pipeline.
+    pub fn new(ctx: &mut Context) -> Self {
+        let mut imgui = ImGui::init();
+        imgui.set_ini_filename(None);
+
+        let factory = graphics::get_factory(ctx);
+        let target = graphics::get_screen_render_target(ctx);
+        let renderer = Renderer::init(&mut imgui, factory, Shaders::GlSl150, target)
+            .expect("failed to initialize imgui renderer");
+
+        LiveDebugger { imgui, renderer }
+    }
+
+    /// Draw the overlay and fold its buttons into `DebugCommands`. Sliders
+    /// write straight into `tunables`; the caller applies them to the
+    /// relevant `RigidBody` setters and reads `ball_speed` back out for the
+    /// readout that used to be a bare `println!`. `screen_width`/
+    /// `screen_height` are the scene's real viewport
+    /// (`MAX_SCREEN_WIDTH`/`MAX_SCREEN_HEIGHT` at most, but often smaller),
+    /// so imgui lays the overlay out against the window that's actually on
+    /// screen instead of an assumed size.
+    pub fn draw(
+        &mut self,
+        ctx: &mut Context,
+        tunables: &mut Tunables,
+        ball_speed: f32,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> GameResult<DebugCommands> {
+        let mut commands = DebugCommands::default();
+
+        let frame_size = imgui::FrameSize::new(screen_width as f64, screen_height as f64, 1.0);
+        let ui: Ui = self.imgui.frame(frame_size, 1.0 / 60.0);
+        ui.window(im_str!("Live Debugger"))
+            .size((260.0, 280.0), imgui::ImGuiCondition::FirstUseEver)
+            .build(|| {
+                ui.slider_float(im_str!("Paddle speed"), &mut tunables.paddle_speed, 100.0, 3000.0)
+                    .build();
+                ui.slider_float(im_str!("Lerp tweak"), &mut tunables.lerp_tweak, 1.0, 30.0)
+                    .build();
+                ui.slider_float(im_str!("Ball speed"), &mut tunables.ball_speed, 200.0, 3000.0)
+                    .build();
+                ui.slider_float(im_str!("Restitution"), &mut tunables.restitution, 0.0, 2.0)
+                    .build();
+                ui.slider_float(im_str!("Mass"), &mut tunables.mass, 0.1, 10.0)
+                    .build();
+                ui.slider_float(im_str!("Gravity"), &mut tunables.gravity, -2000.0, 2000.0)
+                    .build();
+
+                ui.separator();
+                ui.text(im_str!("Ball speed: {:.1}", ball_speed));
+
+                // Resuming is Escape/P in the `PauseScene` this pushes, same
+                // as if the player had paused without touching the overlay
+                // at all, so there's a single place that knows what "paused"
+                // means.
+                if ui.small_button(im_str!("Pause")) {
+                    commands.toggle_pause = true;
+                }
+                ui.same_line(0.0);
+                if ui.small_button(im_str!("Re-serve")) {
+                    commands.reserve = true;
+                }
+            });
+
+        let factory = graphics::get_factory(ctx);
+        let encoder = graphics::get_encoder(ctx);
+        self.renderer
+            .render(ui, factory, encoder)
+            .expect("imgui render failed");
+
+        Ok(commands)
+    }
+}
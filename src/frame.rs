@@ -0,0 +1,49 @@
+use ggez::graphics::Vector2;
+
+use lerp::Lerp;
+
+/// Ball-following camera. Lerps toward a target that's clamped to the map
+/// bounds, so the view never scrolls past the walls even when the arena is
+/// bigger than the window.
+pub struct Frame {
+    target_x: f32,
+    target_y: f32,
+    x: f32,
+    y: f32,
+}
+
+impl Frame {
+    pub fn new() -> Self {
+        Frame {
+            target_x: 0.0,
+            target_y: 0.0,
+            x: 0.0,
+            y: 0.0,
+        }
+    }
+
+    /// `focus` and every dimension are in raw (unscaled) map units.
+    pub fn update(
+        &mut self,
+        focus: Vector2<f32>,
+        map_width: f32,
+        map_height: f32,
+        viewport_width: f32,
+        viewport_height: f32,
+        seconds: f32,
+    ) {
+        const CAMERA_LERP: f32 = 4.0;
+
+        let max_x = (map_width - viewport_width).max(0.0);
+        let max_y = (map_height - viewport_height).max(0.0);
+        self.target_x = (focus.x - viewport_width / 2.0).max(0.0).min(max_x);
+        self.target_y = (focus.y - viewport_height / 2.0).max(0.0).min(max_y);
+
+        self.x = self.x.lerp(self.target_x, CAMERA_LERP * seconds);
+        self.y = self.y.lerp(self.target_y, CAMERA_LERP * seconds);
+    }
+
+    pub fn offset(&self) -> Vector2<f32> {
+        Vector2::new(self.x, self.y)
+    }
+}
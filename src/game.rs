@@ -0,0 +1,77 @@
+use ggez::event::{EventHandler, Keycode, Mod};
+use ggez::graphics;
+use ggez::{Context, GameResult};
+
+use scene::{Scene, SharedResources, Transition};
+use scenes::title::TitleScene;
+
+/// Top-level `EventHandler`: owns the scene stack and the resources every
+/// scene shares, and dispatches ticks/events to whichever scene is on top.
+pub struct Game {
+    scenes: Vec<Box<Scene>>,
+    shared: SharedResources,
+}
+
+impl Game {
+    pub fn new(map: tiled::Map, scaling_factor: f32, screen_width: f32, screen_height: f32) -> Self {
+        Game {
+            scenes: vec![Box::new(TitleScene::new())],
+            shared: SharedResources {
+                map,
+                scaling_factor,
+                screen_width,
+                screen_height,
+            },
+        }
+    }
+
+    fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Switch(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+        }
+    }
+}
+
+impl EventHandler for Game {
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.update(ctx, &mut self.shared)?,
+            None => Transition::None,
+        };
+        self.apply(transition);
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        graphics::clear(ctx);
+        // Draw the whole stack bottom-to-top so a pause overlay still
+        // shows the (frozen) match underneath it.
+        for scene in &mut self.scenes {
+            scene.draw(ctx, &self.shared)?;
+        }
+        graphics::present(ctx);
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: Keycode, keymod: Mod, repeat: bool) {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.key_down(ctx, &mut self.shared, keycode, keymod, repeat),
+            None => Transition::None,
+        };
+        self.apply(transition);
+    }
+
+    fn key_up_event(&mut self, ctx: &mut Context, keycode: Keycode, keymod: Mod, repeat: bool) {
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.key_up(ctx, &mut self.shared, keycode, keymod, repeat);
+        }
+    }
+}
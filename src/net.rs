@@ -0,0 +1,219 @@
+//! Minimal GGRS-style rollback session: buffer per-frame inputs, detect
+//! mispredictions from the remote peer, and expose which frame to roll
+//! back to so `PlayScene` can re-simulate deterministically.
+
+use std::net::{SocketAddr, UdpSocket};
+use std::time::Duration;
+
+/// One frame of paddle input, packed into a single byte so it fits in a
+/// UDP datagram alongside the frame number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Input(u8);
+
+impl Input {
+    const UP: u8 = 1;
+    const DOWN: u8 = 2;
+
+    pub fn from_axis(axis: f32) -> Self {
+        if axis < 0.0 {
+            Input(Input::UP)
+        } else if axis > 0.0 {
+            Input(Input::DOWN)
+        } else {
+            Input(0)
+        }
+    }
+
+    pub fn axis(&self) -> f32 {
+        match self.0 {
+            Input::UP => -1.0,
+            Input::DOWN => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    fn to_byte(&self) -> u8 {
+        self.0
+    }
+
+    fn from_byte(byte: u8) -> Self {
+        Input(byte)
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input(0)
+    }
+}
+
+/// Ring buffer depth for both the snapshot history and the input history;
+/// we never need to roll back further than this many frames.
+pub const MAX_PREDICTION_WINDOW: usize = 8;
+
+pub struct RollbackSession {
+    socket: UdpSocket,
+    remote_addr: SocketAddr,
+    pub num_players: u32,
+    pub input_delay: u32,
+    /// Local inputs, indexed by `frame % MAX_PREDICTION_WINDOW`.
+    local_inputs: [Input; MAX_PREDICTION_WINDOW],
+    /// Remote inputs as last predicted for each slot. A frame with no
+    /// confirmed input is predicted to repeat what its slot last held, i.e.
+    /// the value for the frame `MAX_PREDICTION_WINDOW` earlier.
+    predicted_remote: [Input; MAX_PREDICTION_WINDOW],
+    /// Remote inputs once confirmed over the wire, tagged with the frame
+    /// they belong to, if any arrived yet.
+    confirmed_remote: [Option<(u32, Input)>; MAX_PREDICTION_WINDOW],
+}
+
+/// One byte that means nothing but "I'm here", exchanged before either side
+/// counts a single simulation frame.
+const SYNC_MAGIC: u8 = 0xff;
+
+/// How long to wait for each sync reply, and how many times to retry
+/// before giving up and playing offline. Bounded so that starting a match
+/// with no peer listening at `remote_addr` (the common case at the title
+/// screen, where nothing has bound the other side yet) degrades to solo
+/// play instead of freezing the process forever.
+const SYNC_READ_TIMEOUT: Duration = Duration::from_millis(100);
+const SYNC_ATTEMPTS: u32 = 20;
+
+impl RollbackSession {
+    /// Build a session from `PONG_BIND_ADDR`/`PONG_REMOTE_ADDR`/
+    /// `PONG_INPUT_DELAY`, so the two sides of a real match can each bind
+    /// their own address and point at each other instead of both hardcoding
+    /// the same loopback pair. Defaults bind `0.0.0.0:7777` and point at
+    /// `127.0.0.1:7778` with an input delay of 2.
+    pub fn from_env() -> std::io::Result<Self> {
+        let bind_addr =
+            std::env::var("PONG_BIND_ADDR").unwrap_or_else(|_| "0.0.0.0:7777".to_string());
+        let remote_addr =
+            std::env::var("PONG_REMOTE_ADDR").unwrap_or_else(|_| "127.0.0.1:7778".to_string());
+        let input_delay = std::env::var("PONG_INPUT_DELAY")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(2);
+        Self::new(&bind_addr, &remote_addr, 2, input_delay)
+    }
+
+    /// `num_players` only ever takes the value 2: a `RollbackSession` is a
+    /// direct peer-to-peer link between exactly two instances. It's taken
+    /// as a parameter (rather than just assumed) so the constructor fails
+    /// loudly if that ever stops being true, the same way GGRS itself takes
+    /// it as session config rather than a hidden constant.
+    pub fn new(
+        bind_addr: &str,
+        remote_addr: &str,
+        num_players: u32,
+        input_delay: u32,
+    ) -> std::io::Result<Self> {
+        assert_eq!(num_players, 2, "RollbackSession only supports 1v1 play");
+        assert!(
+            (input_delay as usize) < MAX_PREDICTION_WINDOW,
+            "input_delay ({}) must be less than MAX_PREDICTION_WINDOW ({})",
+            input_delay,
+            MAX_PREDICTION_WINDOW
+        );
+
+        let socket = UdpSocket::bind(bind_addr)?;
+        let remote_addr: SocketAddr = remote_addr.parse().expect("invalid remote addr");
+        if !Self::sync_clocks(&socket, remote_addr)? {
+            eprintln!(
+                "no peer answered at {} within {:?}, continuing offline",
+                remote_addr,
+                SYNC_READ_TIMEOUT * SYNC_ATTEMPTS
+            );
+        }
+        socket.set_nonblocking(true)?;
+
+        Ok(RollbackSession {
+            socket,
+            remote_addr,
+            num_players,
+            input_delay,
+            local_inputs: [Input::default(); MAX_PREDICTION_WINDOW],
+            predicted_remote: [Input::default(); MAX_PREDICTION_WINDOW],
+            confirmed_remote: [None; MAX_PREDICTION_WINDOW],
+        })
+    }
+
+    /// Try to shake hands with the remote peer so both sides start counting
+    /// frames from the same zero point instead of racing ahead
+    /// independently and calling two unrelated counters "frame N". Gives up
+    /// after `SYNC_ATTEMPTS` and returns `false` rather than blocking
+    /// forever, so the caller can fall back to solo play.
+    fn sync_clocks(socket: &UdpSocket, remote_addr: SocketAddr) -> std::io::Result<bool> {
+        socket.set_read_timeout(Some(SYNC_READ_TIMEOUT))?;
+        let mut buf = [0u8; 1];
+        for _ in 0..SYNC_ATTEMPTS {
+            socket.send_to(&[SYNC_MAGIC], remote_addr)?;
+            match socket.recv_from(&mut buf) {
+                Ok((1, from)) if from == remote_addr && buf[0] == SYNC_MAGIC => return Ok(true),
+                _ => continue,
+            }
+        }
+        Ok(false)
+    }
+
+    fn slot(frame: u32) -> usize {
+        frame as usize % MAX_PREDICTION_WINDOW
+    }
+
+    /// Record our own input for `frame` and send it to the peer.
+    pub fn send_local_input(&mut self, frame: u32, input: Input) {
+        self.local_inputs[Self::slot(frame)] = input;
+
+        let mut packet = [0u8; 5];
+        packet[0..4].copy_from_slice(&frame.to_le_bytes());
+        packet[4] = input.to_byte();
+        let _ = self.socket.send_to(&packet, self.remote_addr);
+    }
+
+    /// Drain any datagrams that have arrived, confirming remote inputs.
+    pub fn poll(&mut self) {
+        let mut buf = [0u8; 5];
+        while let Ok((len, _)) = self.socket.recv_from(&mut buf) {
+            if len != 5 {
+                continue;
+            }
+            let frame = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+            let input = Input::from_byte(buf[4]);
+            self.confirmed_remote[Self::slot(frame)] = Some((frame, input));
+        }
+    }
+
+    /// The input we should simulate `frame` with: confirmed if we have it,
+    /// otherwise the last value we predicted for that slot.
+    pub fn remote_input(&self, frame: u32) -> Input {
+        let slot = Self::slot(frame);
+        match self.confirmed_remote[slot] {
+            Some((confirmed_frame, confirmed)) if confirmed_frame == frame => confirmed,
+            _ => self.predicted_remote[slot],
+        }
+    }
+
+    pub fn local_input(&self, frame: u32) -> Input {
+        self.local_inputs[Self::slot(frame)]
+    }
+
+    /// Remember what we predicted for `frame` so a later mismatch can be
+    /// detected once the real value is confirmed.
+    pub fn set_predicted_remote(&mut self, frame: u32, input: Input) {
+        self.predicted_remote[Self::slot(frame)] = input;
+    }
+
+    /// If the confirmed input for `frame` differs from what we predicted,
+    /// return it so the caller can roll back and resimulate.
+    pub fn mispredicted_at(&self, frame: u32) -> Option<Input> {
+        let slot = Self::slot(frame);
+        match self.confirmed_remote[slot] {
+            Some((confirmed_frame, confirmed))
+                if confirmed_frame == frame && confirmed != self.predicted_remote[slot] =>
+            {
+                Some(confirmed)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use ggez::event::{Keycode, Mod};
+use ggez::{Context, GameResult};
+
+/// Resources shared by every scene on the stack, as opposed to a scene's
+/// own transient state (physics world, menu cursor, etc).
+pub struct SharedResources {
+    pub map: tiled::Map,
+    pub scaling_factor: f32,
+    /// Window/screen-coordinate size, already in post-scale units. May be
+    /// smaller than the map itself, in which case a scene's camera needs
+    /// to pan around instead of showing the whole arena at once.
+    pub screen_width: f32,
+    pub screen_height: f32,
+}
+
+/// What the stack should do after a scene handles an event or a tick.
+pub enum Transition {
+    None,
+    Push(Box<Scene>),
+    Pop,
+    Switch(Box<Scene>),
+}
+
+/// A single layer of the game: the title screen, the match itself, a pause
+/// overlay, etc. `Game` drives a stack of these instead of one monolithic
+/// `EventHandler`.
+pub trait Scene {
+    fn update(&mut self, ctx: &mut Context, shared: &mut SharedResources) -> GameResult<Transition>;
+    fn draw(&mut self, ctx: &mut Context, shared: &SharedResources) -> GameResult<()>;
+
+    fn key_down(
+        &mut self,
+        _ctx: &mut Context,
+        _shared: &mut SharedResources,
+        _keycode: Keycode,
+        _keymod: Mod,
+        _repeat: bool,
+    ) -> Transition {
+        Transition::None
+    }
+
+    fn key_up(
+        &mut self,
+        _ctx: &mut Context,
+        _shared: &mut SharedResources,
+        _keycode: Keycode,
+        _keymod: Mod,
+        _repeat: bool,
+    ) {
+    }
+}
@@ -0,0 +1,51 @@
+use ggez::event::{Keycode, Mod};
+use ggez::graphics::{self, Point2, Text};
+use ggez::{Context, GameResult};
+
+use scene::{Scene, SharedResources, Transition};
+use scenes::play::PlayScene;
+
+/// Shown once a match ends, naming the winner and offering a rematch.
+pub struct GameOverScene {
+    winner: String,
+}
+
+impl GameOverScene {
+    pub fn new(winner: String) -> Self {
+        GameOverScene { winner }
+    }
+}
+
+impl Scene for GameOverScene {
+    fn update(
+        &mut self,
+        _ctx: &mut Context,
+        _shared: &mut SharedResources,
+    ) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _shared: &SharedResources) -> GameResult<()> {
+        graphics::clear(ctx);
+        graphics::set_color(ctx, (255, 255, 255).into())?;
+        let font = graphics::Font::default_font()?;
+        let text = Text::new(
+            ctx,
+            &format!("{} wins! -- press any key to play again", self.winner),
+            &font,
+        )?;
+        graphics::draw(ctx, &text, Point2::new(40.0, 40.0), 0.0)?;
+        Ok(())
+    }
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedResources,
+        _keycode: Keycode,
+        _keymod: Mod,
+        _repeat: bool,
+    ) -> Transition {
+        Transition::Switch(Box::new(PlayScene::new(ctx, &shared.map)))
+    }
+}
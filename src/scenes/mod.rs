@@ -0,0 +1,4 @@
+pub mod game_over;
+pub mod pause;
+pub mod play;
+pub mod title;
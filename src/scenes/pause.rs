@@ -0,0 +1,47 @@
+use ggez::event::{Keycode, Mod};
+use ggez::graphics::{self, Point2, Text};
+use ggez::{Context, GameResult};
+
+use scene::{Scene, SharedResources, Transition};
+
+/// Pushed on top of a `PlayScene` to suspend its physics step while still
+/// letting it render (frozen) underneath.
+pub struct PauseScene;
+
+impl PauseScene {
+    pub fn new() -> Self {
+        PauseScene
+    }
+}
+
+impl Scene for PauseScene {
+    fn update(
+        &mut self,
+        _ctx: &mut Context,
+        _shared: &mut SharedResources,
+    ) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _shared: &SharedResources) -> GameResult<()> {
+        graphics::set_color(ctx, (255, 255, 255).into())?;
+        let font = graphics::Font::default_font()?;
+        let text = Text::new(ctx, "Paused -- press Escape to resume", &font)?;
+        graphics::draw(ctx, &text, Point2::new(40.0, 40.0), 0.0)?;
+        Ok(())
+    }
+
+    fn key_down(
+        &mut self,
+        _ctx: &mut Context,
+        _shared: &mut SharedResources,
+        keycode: Keycode,
+        _keymod: Mod,
+        _repeat: bool,
+    ) -> Transition {
+        match keycode {
+            Keycode::Escape | Keycode::P => Transition::Pop,
+            _ => Transition::None,
+        }
+    }
+}
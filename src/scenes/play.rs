@@ -0,0 +1,668 @@
+use ggez::event::{Keycode, Mod};
+use ggez::graphics::{DrawMode, Matrix4, Point2, Rect, Vector2};
+use ggez::nalgebra::{Isometry2, Translation2, Vector3};
+use ggez::timer;
+use ggez::{graphics, Context, GameResult};
+
+use nphysics2d::object::{RigidBody, RigidBodyHandle};
+use nphysics2d::world::World;
+
+use ncollide::query::{self, Proximity};
+use ncollide::shape::{Ball, Ball2, Cuboid, Cuboid2};
+
+use gilrs::{Axis, Button, Gamepad, GamepadId, Gilrs};
+
+use lerp::Lerp;
+
+use debugger::{LiveDebugger, Tunables};
+use frame::Frame;
+use net::{Input, RollbackSession, MAX_PREDICTION_WINDOW};
+use scene::{Scene, SharedResources, Transition};
+use scenes::game_over::GameOverScene;
+use scenes::pause::PauseScene;
+
+/// First side to reach this many points wins the match.
+const TARGET_SCORE: u32 = 11;
+
+/// Where a paddle's per-frame axis value comes from. `Ai` is the default
+/// for a paddle with no local controller: the `ai` paddle is then driven by
+/// the rollback session's remote input, which stays neutral while no peer
+/// has sent anything.
+#[derive(Clone, Copy, PartialEq)]
+enum InputSource {
+    Keyboard,
+    Gamepad(GamepadId),
+    Ai,
+}
+
+struct Paddle {
+    dy: f32,
+    axis: f32,
+    source: InputSource,
+    rb: Option<RigidBodyHandle<f32>>,
+}
+
+impl Default for Paddle {
+    fn default() -> Self {
+        Paddle {
+            dy: 0.0,
+            axis: 0.0,
+            source: InputSource::Ai,
+            rb: None,
+        }
+    }
+}
+
+/// Read a gamepad's left stick Y (preferred) or d-pad into a paddle axis
+/// value, proportional for the stick and full-speed for the d-pad.
+fn gamepad_axis(gamepad: &Gamepad) -> f32 {
+    if let Some(data) = gamepad.axis_data(Axis::LeftStickY) {
+        return -data.value();
+    }
+    if gamepad.is_pressed(Button::DPadUp) {
+        return -1.0;
+    }
+    if gamepad.is_pressed(Button::DPadDown) {
+        return 1.0;
+    }
+    0.0
+}
+
+/// Everything needed to rewind a single rigid body to an earlier frame.
+#[derive(Clone)]
+struct BodySnapshot {
+    position: Isometry2<f32>,
+    lin_vel: Vector2<f32>,
+    ang_vel: f32,
+}
+
+impl BodySnapshot {
+    fn capture(rb: &RigidBody<f32>) -> Self {
+        BodySnapshot {
+            position: *rb.position(),
+            lin_vel: rb.lin_vel(),
+            ang_vel: rb.ang_vel(),
+        }
+    }
+
+    fn restore(&self, rb: &mut RigidBody<f32>) {
+        rb.set_position(self.position);
+        rb.set_lin_vel(self.lin_vel);
+        rb.set_ang_vel(self.ang_vel);
+    }
+}
+
+/// The full deterministic state of the world at one frame, cheap enough to
+/// keep a short ring buffer of so rollback can rewind to it.
+///
+/// `tunables` rides along here too: the debug overlay can change them mid-
+/// match, and `step_frame` is only a pure function of (snapshot, inputs) if
+/// the tunables in effect for a given frame travel with that frame instead
+/// of being read live off whatever the sliders currently say.
+#[derive(Clone)]
+struct Snapshot {
+    player: BodySnapshot,
+    ai: BodySnapshot,
+    ball: BodySnapshot,
+    player_dy: f32,
+    ai_dy: f32,
+    score: (u32, u32),
+    tunables: Tunables,
+}
+
+/// A static sensor region behind a paddle: not a solid rigid body, just an
+/// area checked for ball overlap each frame via an ncollide proximity query.
+struct Goal {
+    position: Isometry2<f32>,
+    shape: Cuboid2<f32>,
+}
+
+impl Goal {
+    fn from_object(object: &tiled::Object) -> Self {
+        let half_extents = match object.shape {
+            tiled::ObjectShape::Rect { width, height } => Vector2::new(width, height) / 2.0,
+            _ => panic!("goal must be rect"),
+        };
+        let position = Isometry2::new(
+            Vector2::new(object.x + half_extents.x, object.y + half_extents.y),
+            0.0,
+        );
+        Goal {
+            position,
+            shape: Cuboid::new(half_extents),
+        }
+    }
+
+    fn contains(&self, ball_position: &Isometry2<f32>, ball_shape: &Ball2<f32>) -> bool {
+        let proximity = query::proximity(&self.position, &self.shape, ball_position, ball_shape, 0.0);
+        proximity == Proximity::Intersecting
+    }
+}
+
+pub struct PlayScene {
+    world: World<f32>,
+    player: Paddle,
+    ball: RigidBodyHandle<f32>,
+    ball_spawn: Isometry2<f32>,
+    ai: Paddle,
+    goal_left: Goal,
+    goal_right: Goal,
+    score: (u32, u32),
+    winner: Option<&'static str>,
+    camera: Frame,
+    gilrs: Gilrs,
+    session: RollbackSession,
+    frame: u32,
+    snapshots: [Option<Snapshot>; MAX_PREDICTION_WINDOW],
+    tunables: Tunables,
+    debugger: LiveDebugger,
+    /// Set by the debug overlay's "Pause" button and consumed by the next
+    /// `update`, which turns it into a real `Transition::Push(PauseScene)`
+    /// instead of a second, parallel notion of "paused".
+    pause_requested: bool,
+    /// Set by the debug overlay's "Re-serve" button and consumed by the
+    /// next `update` rather than acted on immediately in `draw`, so it
+    /// can't reset the ball while some other scene is on top and this
+    /// one's `update` isn't running.
+    reserve_requested: bool,
+}
+
+fn make_cuboid_rb(object: &tiled::Object, dynamic: bool) -> RigidBody<f32> {
+    let half_extents = match object.shape {
+        tiled::ObjectShape::Rect { width, height } => Vector2::new(width, height) / 2.0,
+        _ => panic!("cuboid must be rect"),
+    };
+    let cuboid = Cuboid::new(half_extents);
+
+    let mut rb = if dynamic {
+        RigidBody::new_dynamic(cuboid, 1.0, 1.0, 0.0)
+    } else {
+        RigidBody::new_static(cuboid, 1.0, 0.0)
+    };
+
+    rb.append_translation(&Translation2::new(
+        object.x + half_extents.x,
+        object.y + half_extents.y,
+    ));
+    rb
+}
+
+impl PlayScene {
+    pub fn new(ctx: &mut Context, map: &tiled::Map) -> Self {
+        let mut world = World::new();
+
+        let mut player = Paddle::default();
+        player.source = InputSource::Keyboard;
+        let mut ai = Paddle::default();
+        let mut ball = None;
+        let mut goal_left = None;
+        let mut goal_right = None;
+
+        for group in &map.object_groups {
+            for object in &group.objects {
+                match object.obj_type.as_ref() {
+                    "wall" => {
+                        let mut rb = make_cuboid_rb(object, false);
+                        rb.set_user_data(Some(Box::new(())));
+                        world.add_rigid_body(rb);
+                    }
+                    "paddle" => {
+                        let mut rb = make_cuboid_rb(object, true);
+                        rb.set_inv_mass(0.0);
+
+                        let handle = world.add_rigid_body(rb);
+                        match object.name.as_ref() {
+                            "player_paddle" => {
+                                player.rb = Some(handle);
+                            }
+                            "ai_paddle" => {
+                                ai.rb = Some(handle);
+                            }
+                            _ => panic!("unknown paddle name"),
+                        }
+                    }
+                    "ball" => {
+                        let radius = match object.shape {
+                            tiled::ObjectShape::Ellipse { width, height } => {
+                                width.hypot(height) / 2.0
+                            }
+                            _ => panic!("ball must be an ellipse"),
+                        };
+                        let mut rb = RigidBody::new_dynamic(Ball::new(radius), 1.0, 1.0, 0.0);
+                        rb.append_translation(&Translation2::new(
+                            object.x + radius,
+                            object.y + radius,
+                        ));
+                        // rb.set_inv_mass(std::f32::MAX);
+                        rb.set_lin_vel(Vector2::new(1000.0, 0.0));
+                        ball = Some(world.add_rigid_body(rb));
+                    }
+                    "goal_left" => goal_left = Some(Goal::from_object(object)),
+                    "goal_right" => goal_right = Some(Goal::from_object(object)),
+                    _ => {}
+                }
+            }
+        }
+
+        let ball = ball.unwrap();
+        let ball_spawn = *ball.borrow().position();
+
+        let session =
+            RollbackSession::from_env().expect("failed to start rollback session");
+        let gilrs = Gilrs::new().expect("failed to initialize gilrs");
+
+        PlayScene {
+            world,
+            player,
+            ai,
+            ball,
+            ball_spawn,
+            goal_left: goal_left.expect("map is missing a goal_left object"),
+            goal_right: goal_right.expect("map is missing a goal_right object"),
+            score: (0, 0),
+            winner: None,
+            camera: Frame::new(),
+            gilrs,
+            session,
+            frame: 0,
+            snapshots: Default::default(),
+            tunables: Tunables::default(),
+            debugger: LiveDebugger::new(ctx),
+            pause_requested: false,
+            reserve_requested: false,
+        }
+    }
+
+    /// Capture every dynamic body plus the lerp state that feeds into it.
+    /// The caller stores the result in `self.snapshots[frame % N]`, which
+    /// is what keys it to a frame number.
+    fn save_state(&self) -> Snapshot {
+        let player_rb = self.player.rb.as_ref().unwrap().borrow();
+        let ai_rb = self.ai.rb.as_ref().unwrap().borrow();
+        let ball_rb = self.ball.borrow();
+
+        Snapshot {
+            player: BodySnapshot::capture(&player_rb),
+            ai: BodySnapshot::capture(&ai_rb),
+            ball: BodySnapshot::capture(&ball_rb),
+            player_dy: self.player.dy,
+            ai_dy: self.ai.dy,
+            score: self.score,
+            tunables: self.tunables,
+        }
+    }
+
+    fn load_state(&mut self, snapshot: &Snapshot) {
+        snapshot
+            .player
+            .restore(&mut self.player.rb.as_ref().unwrap().borrow_mut());
+        snapshot
+            .ai
+            .restore(&mut self.ai.rb.as_ref().unwrap().borrow_mut());
+        snapshot.ball.restore(&mut self.ball.borrow_mut());
+        self.player.dy = snapshot.player_dy;
+        self.ai.dy = snapshot.ai_dy;
+        self.score = snapshot.score;
+        self.tunables = snapshot.tunables;
+        self.winner = None;
+    }
+
+    /// Serve direction alternates with total points scored so far, keeping
+    /// the reset itself a pure function of (state) rather than of time.
+    fn reset_ball(&mut self) {
+        let serve_dir = if (self.score.0 + self.score.1) % 2 == 0 {
+            1.0
+        } else {
+            -1.0
+        };
+        let mut rb = self.ball.borrow_mut();
+        rb.set_position(self.ball_spawn);
+        rb.set_lin_vel(Vector2::new(self.tunables.ball_speed * serve_dir, 0.0));
+        rb.set_ang_vel(0.0);
+    }
+
+    /// Hand a newly connected pad to whichever paddle doesn't already have
+    /// one, preferring the `ai` paddle so a second human can join in.
+    fn assign_gamepad(&mut self, id: GamepadId) {
+        if self.ai.source == InputSource::Ai {
+            self.ai.source = InputSource::Gamepad(id);
+        } else if self.player.source == InputSource::Keyboard {
+            self.player.source = InputSource::Gamepad(id);
+        }
+    }
+
+    /// Drain gilrs events and refresh each gamepad-controlled paddle's axis
+    /// from its stick/d-pad for this frame.
+    fn poll_gamepads(&mut self) {
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            if let gilrs::EventType::Connected = event {
+                self.assign_gamepad(id);
+            }
+        }
+
+        for (id, gamepad) in self.gilrs.gamepads() {
+            let axis = gamepad_axis(&gamepad);
+            if self.player.source == InputSource::Gamepad(id) {
+                self.player.axis = axis;
+            } else if self.ai.source == InputSource::Gamepad(id) {
+                self.ai.axis = axis;
+            }
+        }
+    }
+
+    /// Advance the world by exactly one fixed step given the confirmed (or
+    /// predicted) inputs for that frame. Must stay a pure function of
+    /// (current state, inputs) so resimulation during rollback reproduces
+    /// the original run bit-for-bit.
+    fn step_frame(&mut self, player_input: Input, ai_input: Input) {
+        const DESIRED_FPS: f32 = 60.0;
+        let seconds = 1.0 / DESIRED_FPS;
+
+        self.player.dy = self.player.dy.lerp(
+            player_input.axis() * self.tunables.paddle_speed,
+            self.tunables.lerp_tweak * seconds,
+        );
+        self.ai.dy = self.ai.dy.lerp(
+            ai_input.axis() * self.tunables.paddle_speed,
+            self.tunables.lerp_tweak * seconds,
+        );
+
+        if let Some(ref mut player_rb) = self.player.rb {
+            let mut player_rb = player_rb.borrow_mut();
+            player_rb.set_lin_vel(Vector2::new(0.0, self.player.dy));
+            player_rb.set_restitution(self.tunables.restitution);
+        }
+        if let Some(ref mut ai_rb) = self.ai.rb {
+            let mut ai_rb = ai_rb.borrow_mut();
+            ai_rb.set_lin_vel(Vector2::new(0.0, self.ai.dy));
+            ai_rb.set_restitution(self.tunables.restitution);
+        }
+        {
+            let mut ball_rb = self.ball.borrow_mut();
+            ball_rb.set_restitution(self.tunables.restitution);
+            ball_rb.set_inv_mass(1.0 / self.tunables.mass);
+        }
+
+        self.world.set_gravity(Vector2::new(0.0, self.tunables.gravity));
+        self.world.step(0.016);
+
+        // Re-clamp the ball to the tunable speed rather than letting
+        // restitution/gravity tweaks drift it away from a playable pace.
+        {
+            let mut ball_rb = self.ball.borrow_mut();
+            let vel = ball_rb.lin_vel();
+            let speed = vel.x.hypot(vel.y);
+            if speed > 0.0 {
+                ball_rb.set_lin_vel(vel * (self.tunables.ball_speed / speed));
+            }
+        }
+
+        // player_scored is None (no goal), Some(true) (player's opponent
+        // let it through the right goal), or Some(false) (through the left).
+        let player_scored = {
+            let ball_rb = self.ball.borrow();
+            let ball_position = *ball_rb.position();
+            let ball_shape = ball_rb.shape().as_shape::<Ball2<f32>>().unwrap();
+            if self.goal_left.contains(&ball_position, ball_shape) {
+                Some(false)
+            } else if self.goal_right.contains(&ball_position, ball_shape) {
+                Some(true)
+            } else {
+                None
+            }
+        };
+
+        if let Some(player_scored) = player_scored {
+            if player_scored {
+                self.score.0 += 1;
+            } else {
+                self.score.1 += 1;
+            }
+            self.reset_ball();
+
+            if self.score.0 >= TARGET_SCORE {
+                self.winner = Some("Player");
+            } else if self.score.1 >= TARGET_SCORE {
+                self.winner = Some("AI");
+            }
+        }
+    }
+}
+
+impl Scene for PlayScene {
+    fn update(&mut self, ctx: &mut Context, _shared: &mut SharedResources) -> GameResult<Transition> {
+        // The debug overlay's "Pause" button queues this instead of
+        // flipping a flag of our own: pushing `PauseScene` is the same
+        // thing that pressing Escape does, so there's exactly one pause
+        // mechanism, and it works by this scene simply not being ticked
+        // while something else sits on top of it.
+        if self.pause_requested {
+            self.pause_requested = false;
+            return Ok(Transition::Push(Box::new(PauseScene::new())));
+        }
+
+        const DESIRED_FPS: u32 = 60;
+
+        while timer::check_update_time(ctx, DESIRED_FPS) {
+            self.poll_gamepads();
+            self.session.poll();
+
+            if self.reserve_requested {
+                self.reserve_requested = false;
+                self.reset_ball();
+            }
+
+            let frame = self.frame;
+
+            // Our own input is never predicted, so we can afford to delay
+            // it: send what the player does now tagged for `input_delay`
+            // frames in the future, and simulate this frame with whatever
+            // was sent `input_delay` frames ago. That gap is slack for the
+            // remote input to arrive before it's actually needed, masking
+            // latency instead of mispredicting it away.
+            let raw_axis_input = Input::from_axis(self.player.axis);
+            self.session
+                .send_local_input(frame + self.session.input_delay, raw_axis_input);
+            let local_input = self.session.local_input(frame);
+
+            // The overlay may have edited `self.tunables` since the last
+            // frame; that's what should govern *this* frame, but it must
+            // not leak into the resimulation of earlier frames below.
+            let live_tunables = self.tunables;
+
+            // Roll back to the earliest frame whose remote input we
+            // mispredicted, then resimulate forward to the present. Local
+            // inputs are already buffered `input_delay` frames ahead, so the
+            // window shrinks by that much: older slots hold newer inputs.
+            let earliest = frame
+                .saturating_sub(MAX_PREDICTION_WINDOW as u32 - 1 - self.session.input_delay);
+            let mut rewound_to = None;
+            for f in earliest..frame {
+                if let Some(confirmed) = self.session.mispredicted_at(f) {
+                    self.session.set_predicted_remote(f, confirmed);
+                    // Keep the earliest mispredicted frame: that's the
+                    // point history diverged, so it's the one we must
+                    // resimulate from. A later match in this same pass
+                    // would otherwise overwrite it and leave the earlier
+                    // divergence silently baked into history.
+                    if rewound_to.is_none() {
+                        rewound_to = Some(f);
+                    }
+                }
+            }
+            if let Some(f) = rewound_to {
+                // `snapshots[f]` holds the state *after* frame `f` already
+                // ran (with the wrong prediction); load the state from
+                // before it instead, so the loop below simulates `f`
+                // exactly once with the now-corrected input.
+                let base = self.snapshots[f.saturating_sub(1) as usize % MAX_PREDICTION_WINDOW].clone();
+                if let Some(snapshot) = base {
+                    self.load_state(&snapshot);
+                    for resim in f..frame {
+                        let slot = resim as usize % MAX_PREDICTION_WINDOW;
+                        // Replay with the tunables that were active when
+                        // this frame first ran, not the live ones, so a
+                        // slider drag mid-match can't change history.
+                        self.tunables = self.snapshots[slot]
+                            .as_ref()
+                            .map(|snapshot| snapshot.tunables)
+                            .unwrap_or(live_tunables);
+                        let player_input = self.session.local_input(resim);
+                        let ai_input = self.session.remote_input(resim);
+                        self.step_frame(player_input, ai_input);
+                        self.snapshots[slot] = Some(self.save_state());
+                    }
+                }
+            }
+            self.tunables = live_tunables;
+
+            let ai_input = if let InputSource::Gamepad(_) = self.ai.source {
+                Input::from_axis(self.ai.axis)
+            } else {
+                let predicted = self.session.remote_input(frame);
+                self.session.set_predicted_remote(frame, predicted);
+                predicted
+            };
+            self.step_frame(local_input, ai_input);
+            self.snapshots[frame as usize % MAX_PREDICTION_WINDOW] = Some(self.save_state());
+
+            self.frame += 1;
+
+            if let Some(winner) = self.winner {
+                return Ok(Transition::Switch(Box::new(GameOverScene::new(
+                    winner.to_string(),
+                ))));
+            }
+        }
+        Ok(Transition::None)
+    }
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        _shared: &mut SharedResources,
+        keycode: Keycode,
+        _keymod: Mod,
+        _repeat: bool,
+    ) -> Transition {
+        match keycode {
+            Keycode::Up => {
+                self.player.axis = -1.0;
+                Transition::None
+            }
+            Keycode::Down => {
+                self.player.axis = 1.0;
+                Transition::None
+            }
+            Keycode::Escape => Transition::Push(Box::new(PauseScene::new())),
+            Keycode::Q => {
+                ctx.quit().unwrap();
+                Transition::None
+            }
+            _ => Transition::None, // Do nothing
+        }
+    }
+
+    fn key_up(
+        &mut self,
+        _ctx: &mut Context,
+        _shared: &mut SharedResources,
+        _keycode: Keycode,
+        _keymod: Mod,
+        _repeat: bool,
+    ) {
+        self.player.axis = 0.0;
+    }
+
+    fn draw(&mut self, ctx: &mut Context, shared: &SharedResources) -> GameResult<()> {
+        let scale_only = Matrix4::new_scaling(shared.scaling_factor);
+        graphics::set_transform(ctx, scale_only);
+        graphics::apply_transformations(ctx)?;
+
+        graphics::set_color(ctx, (255, 255, 255).into())?;
+        let font = graphics::Font::default_font()?;
+        let score = graphics::Text::new(
+            ctx,
+            &format!("{} - {}", self.score.0, self.score.1),
+            &font,
+        )?;
+        graphics::draw(ctx, &score, Point2::new(20.0, 10.0), 0.0)?;
+
+        // Track the ball, clamped to the map bounds; when the map fits on
+        // screen this just settles at (0, 0) and nothing visibly pans.
+        let focus = self.ball.borrow().position().translation.vector;
+        let map_width = (shared.map.width * shared.map.tile_width) as f32;
+        let map_height = (shared.map.height * shared.map.tile_height) as f32;
+        let viewport_width = shared.screen_width / shared.scaling_factor;
+        let viewport_height = shared.screen_height / shared.scaling_factor;
+        self.camera.update(
+            focus,
+            map_width,
+            map_height,
+            viewport_width,
+            viewport_height,
+            1.0 / 60.0,
+        );
+
+        let offset = self.camera.offset();
+        let camera_transform =
+            scale_only * Matrix4::new_translation(&Vector3::new(-offset.x, -offset.y, 0.0));
+        graphics::set_transform(ctx, camera_transform);
+        graphics::apply_transformations(ctx)?;
+
+        for body in self.world.rigid_bodies() {
+            let body = body.borrow();
+            if body.user_data().is_some() {
+                graphics::set_color(ctx, (100, 100, 100).into())?;
+            } else {
+                graphics::set_color(ctx, (255, 255, 255).into())?;
+            }
+            if let Some(shape) = body.shape().as_shape::<Cuboid2<f32>>() {
+                let h = shape.half_extents();
+                let pos = body.position().translation.vector;
+                graphics::rectangle(
+                    ctx,
+                    DrawMode::Fill,
+                    Rect {
+                        x: pos.x - h.x,
+                        y: pos.y - h.y,
+                        w: h.x * 2.0,
+                        h: h.y * 2.0,
+                    },
+                )?;
+            } else if let Some(shape) = body.shape().as_shape::<Ball2<f32>>() {
+                let pos = body.position().translation.vector;
+                let radius = shape.radius();
+                graphics::circle(
+                    ctx,
+                    DrawMode::Fill,
+                    Point2::from_coordinates(pos),
+                    radius,
+                    0.1,
+                )?;
+            }
+        }
+
+        // Leave the transform as the rest of the scene stack expects it:
+        // scaled, but not panned by this scene's camera.
+        graphics::set_transform(ctx, scale_only);
+        graphics::apply_transformations(ctx)?;
+
+        let vel = self.ball.borrow().lin_vel();
+        let ball_speed = vel.x.hypot(vel.y);
+        // Just queue what was clicked; `update` is what's allowed to act on
+        // it, since `update` is the part of this scene that stops running
+        // while it's paused.
+        let commands = self.debugger.draw(
+            ctx,
+            &mut self.tunables,
+            ball_speed,
+            shared.screen_width,
+            shared.screen_height,
+        )?;
+        self.pause_requested |= commands.toggle_pause;
+        self.reserve_requested |= commands.reserve;
+
+        Ok(())
+    }
+}
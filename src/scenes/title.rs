@@ -0,0 +1,44 @@
+use ggez::event::{Keycode, Mod};
+use ggez::graphics::{self, Point2, Text};
+use ggez::{Context, GameResult};
+
+use scene::{Scene, SharedResources, Transition};
+use scenes::play::PlayScene;
+
+/// Press-to-start splash shown before the first match.
+pub struct TitleScene;
+
+impl TitleScene {
+    pub fn new() -> Self {
+        TitleScene
+    }
+}
+
+impl Scene for TitleScene {
+    fn update(
+        &mut self,
+        _ctx: &mut Context,
+        _shared: &mut SharedResources,
+    ) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _shared: &SharedResources) -> GameResult<()> {
+        graphics::set_color(ctx, (255, 255, 255).into())?;
+        let font = graphics::Font::default_font()?;
+        let text = Text::new(ctx, "Pong! -- press any key to start", &font)?;
+        graphics::draw(ctx, &text, Point2::new(40.0, 40.0), 0.0)?;
+        Ok(())
+    }
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedResources,
+        _keycode: Keycode,
+        _keymod: Mod,
+        _repeat: bool,
+    ) -> Transition {
+        Transition::Switch(Box::new(PlayScene::new(ctx, &shared.map)))
+    }
+}